@@ -0,0 +1,95 @@
+use crate::backlight::Backlight;
+use crate::curve::{self, Point};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+/// Sampling delay used right after a large adjustment, to track a fast
+/// ambient-light change without visible lag.
+const FAST_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Sampling delay used once brightness is close to its target, to avoid
+/// needlessly busy-polling the sensor.
+const SLOW_INTERVAL: Duration = Duration::from_millis(2000);
+
+/// A brightness delta at or above this magnitude is considered "large"
+/// enough to warrant the fast sampling loop.
+const FAST_THRESHOLD: u8 = 10;
+
+const IIO_BASE_DIR: &str = "/sys/bus/iio/devices";
+
+/// Auto-detect an ambient light sensor by scanning
+/// `/sys/bus/iio/devices/*/in_illuminance_raw`.
+fn detect_sensor() -> Option<PathBuf> {
+    let entries = fs::read_dir(IIO_BASE_DIR).ok()?;
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().join("in_illuminance_raw"))
+        .find(|path| path.is_file())
+}
+
+/// Read the current illuminance in lux from an `in_illuminance_raw` file.
+fn read_lux(path: &Path) -> Option<f64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Pick the per-iteration sampling delay: fast while still chasing a large
+/// delta, slow once brightness has settled near the target.
+fn sample_interval(delta: i16) -> Duration {
+    if delta.unsigned_abs() >= FAST_THRESHOLD as u16 {
+        FAST_INTERVAL
+    } else {
+        SLOW_INTERVAL
+    }
+}
+
+/// Pick how far to move this iteration: a fraction of the remaining delta,
+/// so large deltas ease in with big steps and small deltas creep by one.
+fn step_size(delta: i16) -> u8 {
+    let magnitude = delta.unsigned_abs();
+    ((magnitude as f64 * 0.3).ceil() as u16).clamp(1, magnitude) as u8
+}
+
+/// Run ambient-light auto-brightness: read the sensor, map lux to a target
+/// percentage via the configured curve, and ease the backend toward it.
+/// Runs until it receives SIGTERM/SIGINT.
+pub fn run(backend: &dyn Backlight, sensor: Option<&str>, overrides: &[Point], shutdown: &AtomicBool) {
+    let sensor_path = match sensor.map(PathBuf::from).or_else(detect_sensor) {
+        Some(path) => path,
+        None => {
+            eprintln!("Error: no ambient light sensor found; pass --sensor to specify one.");
+            return;
+        }
+    };
+
+    let points = curve::merge(curve::default_points(), overrides);
+
+    while !shutdown.load(Ordering::Relaxed) {
+        let Some(lux) = read_lux(&sensor_path) else {
+            eprintln!("Error: failed to read {}.", sensor_path.display());
+            thread::sleep(SLOW_INTERVAL);
+            continue;
+        };
+        let Some(current) = backend.get_brightness() else {
+            thread::sleep(SLOW_INTERVAL);
+            continue;
+        };
+
+        let target = curve::evaluate(&points, lux).round() as u8;
+        let delta = target as i16 - current as i16;
+
+        if delta != 0 {
+            let step = step_size(delta).min(delta.unsigned_abs() as u8);
+            let next = if delta > 0 {
+                current.saturating_add(step)
+            } else {
+                current.saturating_sub(step)
+            };
+            backend.set_brightness(next, 0, 1);
+        }
+
+        thread::sleep(sample_interval(delta));
+    }
+}
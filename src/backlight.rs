@@ -0,0 +1,460 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A source/sink of display brightness, abstracting over the different
+/// ways this tool can query and drive the hardware (xbacklight, sysfs, ...).
+pub trait Backlight {
+    /// Retrieve the current brightness as a percentage (0-100).
+    fn get_brightness(&self) -> Option<u8>;
+
+    /// Set the brightness to an absolute percentage, fading over
+    /// `fade_time` milliseconds in `steps` increments where supported.
+    fn set_brightness(&self, brightness: u8, fade_time: u32, steps: u32) -> bool;
+
+    /// Adjust the brightness relative to its current value. The default
+    /// implementation reads the current value and delegates to
+    /// `set_brightness`, which is correct for any backend that can only
+    /// set absolute levels.
+    fn adjust_brightness(&self, delta: i16, fade_time: u32, steps: u32) -> bool {
+        let Some(current) = self.get_brightness() else {
+            return false;
+        };
+        let target = (current as i16 + delta).clamp(0, 100) as u8;
+        self.set_brightness(target, fade_time, steps)
+    }
+
+    /// The filesystem path this backend's brightness file lives at, if any.
+    /// `--watch` uses this to watch for changes via inotify instead of
+    /// falling back to polling on an interval.
+    fn watch_path(&self) -> Option<PathBuf> {
+        None
+    }
+}
+
+/// Drives the display by writing directly to a `/sys/class/backlight/<device>`
+/// directory, which works regardless of the display server in use.
+pub struct Sysfs {
+    device_dir: PathBuf,
+    max_brightness: u32,
+}
+
+impl Sysfs {
+    const BASE_DIR: &'static str = "/sys/class/backlight";
+
+    /// Build a `Sysfs` backend for an explicit device name under
+    /// `/sys/class/backlight`.
+    pub fn with_device(device: &str) -> Option<Self> {
+        Self::from_dir(Path::new(Self::BASE_DIR).join(device))
+    }
+
+    /// Auto-detect a backlight device by taking the first entry under
+    /// `/sys/class/backlight` that has a readable `max_brightness`.
+    pub fn detect() -> Option<Self> {
+        let entries = fs::read_dir(Self::BASE_DIR).ok()?;
+        entries
+            .filter_map(|entry| entry.ok())
+            .find_map(|entry| Self::from_dir(entry.path()))
+    }
+
+    fn from_dir(device_dir: PathBuf) -> Option<Self> {
+        let max_brightness = fs::read_to_string(device_dir.join("max_brightness"))
+            .ok()?
+            .trim()
+            .parse()
+            .ok()?;
+
+        Some(Self {
+            device_dir,
+            max_brightness,
+        })
+    }
+
+    /// Whether this device's `brightness` file can be written to, used by
+    /// `--backend auto` to decide whether sysfs is usable.
+    pub fn is_writable(&self) -> bool {
+        fs::OpenOptions::new()
+            .write(true)
+            .open(self.device_dir.join("brightness"))
+            .is_ok()
+    }
+
+    fn raw_brightness(&self) -> Option<u32> {
+        fs::read_to_string(self.device_dir.join("brightness"))
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    fn write_raw(&self, raw: u32) -> bool {
+        fs::write(self.device_dir.join("brightness"), raw.to_string().as_bytes()).is_ok()
+    }
+
+    /// Mirrors `ExternalProgram::warn_if_fade_ignored`: sysfs has no native
+    /// fade support, so warn when a non-default `--fade`/`--steps` was
+    /// requested instead of silently dropping it. A `fade_time` of 0 asks
+    /// for no fade at all, so nothing is ignored and no warning is printed.
+    fn warn_if_fade_ignored(fade_time: u32, steps: u32) {
+        if fade_time > 0 && (fade_time != DEFAULT_FADE_TIME || steps != DEFAULT_STEPS) {
+            eprintln!("Warning: --fade/--steps are ignored by the sysfs backend.");
+        }
+    }
+}
+
+impl Backlight for Sysfs {
+    fn get_brightness(&self) -> Option<u8> {
+        let raw = self.raw_brightness()?;
+        let percent = (raw as f64 / self.max_brightness as f64) * 100.0;
+        Some(percent.round() as u8)
+    }
+
+    /// Guarantees a nonzero `brightness` that differs from the current
+    /// percentage never rounds to the same raw value the device is already
+    /// at, which a small `max_brightness` (a 7-255 range is common) can
+    /// otherwise do; the device then moves one raw unit toward the request.
+    fn set_brightness(&self, brightness: u8, fade_time: u32, steps: u32) -> bool {
+        Self::warn_if_fade_ignored(fade_time, steps);
+
+        let raw = ((brightness as f64 / 100.0) * self.max_brightness as f64).round() as u32;
+        let raw = match (self.raw_brightness(), self.get_brightness()) {
+            (Some(current), Some(percent)) if brightness > 0 && raw == current => {
+                if brightness > percent && current < self.max_brightness {
+                    current + 1
+                } else if brightness < percent && current > 0 {
+                    current - 1
+                } else {
+                    raw
+                }
+            }
+            _ => raw,
+        };
+        self.write_raw(raw.min(self.max_brightness))
+    }
+
+    /// Adjust relative to the current raw value rather than its rounded
+    /// percentage, guaranteeing any nonzero `delta` moves the hardware by
+    /// at least one raw unit.
+    fn adjust_brightness(&self, delta: i16, fade_time: u32, steps: u32) -> bool {
+        Self::warn_if_fade_ignored(fade_time, steps);
+
+        let Some(raw) = self.raw_brightness() else {
+            return false;
+        };
+
+        let raw_delta = (delta as f64 / 100.0) * self.max_brightness as f64;
+        let raw_delta = match delta {
+            0 => 0,
+            _ if raw_delta.round() == 0.0 => delta.signum() as i64,
+            _ => raw_delta.round() as i64,
+        };
+
+        let target = (raw as i64 + raw_delta).clamp(0, self.max_brightness as i64) as u32;
+        self.write_raw(target)
+    }
+
+    fn watch_path(&self) -> Option<PathBuf> {
+        Some(self.device_dir.join("brightness"))
+    }
+}
+
+/// Fade defaults applied when neither the CLI nor the config file specify
+/// one; shared with `main.rs` so `warn_if_fade_ignored` can tell a merely
+/// defaulted value apart from one the user actually asked for.
+pub const DEFAULT_FADE_TIME: u32 = 100;
+pub const DEFAULT_STEPS: u32 = 25;
+
+/// External brightness program to shell out to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ProgramKind {
+    Xbacklight,
+    Light,
+    Brightnessctl,
+}
+
+impl ProgramKind {
+    /// Whether this program accepts a fade time/steps for its operations.
+    /// Only `xbacklight` supports fading; `light` and `brightnessctl` apply
+    /// changes immediately.
+    fn supports_fade(self) -> bool {
+        matches!(self, ProgramKind::Xbacklight)
+    }
+}
+
+/// Drives the display via an external brightness command-line tool
+/// (`xbacklight`, `light`, or `brightnessctl`).
+pub struct ExternalProgram {
+    program: ProgramKind,
+}
+
+impl ExternalProgram {
+    pub fn new(program: ProgramKind) -> Self {
+        Self { program }
+    }
+
+    /// Only warns when `fade_time`/`steps` were actually requested (i.e.
+    /// differ from the defaults), since most users never pass `--fade` or
+    /// `--steps` at all and would otherwise see this on every invocation.
+    /// A `fade_time` of 0 (as `--dim` and `--auto` pass for each of their
+    /// own steps) asks for no fade, so it never warns.
+    fn warn_if_fade_ignored(&self, fade_time: u32, steps: u32) {
+        let requested = fade_time > 0 && (fade_time != DEFAULT_FADE_TIME || steps != DEFAULT_STEPS);
+        if requested && !self.program.supports_fade() {
+            eprintln!(
+                "Warning: --fade/--steps are ignored by the {:?} backend.",
+                self.program
+            );
+        }
+    }
+}
+
+impl Backlight for ExternalProgram {
+    fn get_brightness(&self) -> Option<u8> {
+        match self.program {
+            ProgramKind::Xbacklight => {
+                let output = Command::new("xbacklight").arg("-get").output().ok()?;
+                if !output.status.success() {
+                    return None;
+                }
+                let value: f64 = String::from_utf8_lossy(&output.stdout).trim().parse().ok()?;
+                Some(value.round() as u8)
+            }
+            ProgramKind::Light => {
+                let output = Command::new("light").arg("-G").output().ok()?;
+                if !output.status.success() {
+                    return None;
+                }
+                let value: f64 = String::from_utf8_lossy(&output.stdout).trim().parse().ok()?;
+                Some(value.round() as u8)
+            }
+            ProgramKind::Brightnessctl => {
+                let output = Command::new("brightnessctl")
+                    .arg("-m")
+                    .arg("info")
+                    .output()
+                    .ok()?;
+                if !output.status.success() {
+                    return None;
+                }
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                let percentage_field = stdout.trim().lines().next()?.split(',').nth(3)?;
+                let value: f64 = percentage_field.trim_end_matches('%').parse().ok()?;
+                Some(value.round() as u8)
+            }
+        }
+    }
+
+    fn set_brightness(&self, brightness: u8, fade_time: u32, steps: u32) -> bool {
+        self.warn_if_fade_ignored(fade_time, steps);
+
+        let status = match self.program {
+            ProgramKind::Xbacklight => Command::new("xbacklight")
+                .arg("-set")
+                .arg(brightness.to_string())
+                .arg("-time")
+                .arg(fade_time.to_string())
+                .arg("-steps")
+                .arg(steps.to_string())
+                .status(),
+            ProgramKind::Light => Command::new("light")
+                .arg("-S")
+                .arg(brightness.to_string())
+                .status(),
+            ProgramKind::Brightnessctl => Command::new("brightnessctl")
+                .arg("set")
+                .arg(format!("{}%", brightness))
+                .status(),
+        };
+
+        status.is_ok_and(|s| s.success())
+    }
+
+    fn adjust_brightness(&self, delta: i16, fade_time: u32, steps: u32) -> bool {
+        self.warn_if_fade_ignored(fade_time, steps);
+
+        let status = match self.program {
+            ProgramKind::Xbacklight => {
+                let mut cmd = Command::new("xbacklight");
+                if delta >= 0 {
+                    cmd.arg("-inc").arg(delta.to_string());
+                } else {
+                    cmd.arg("-dec").arg((-delta).to_string());
+                }
+                cmd.arg("-time").arg(fade_time.to_string());
+                cmd.arg("-steps").arg(steps.to_string());
+                cmd.status()
+            }
+            ProgramKind::Light => {
+                let mut cmd = Command::new("light");
+                if delta >= 0 {
+                    cmd.arg("-A").arg(delta.to_string());
+                } else {
+                    cmd.arg("-U").arg((-delta).to_string());
+                }
+                cmd.status()
+            }
+            ProgramKind::Brightnessctl => Command::new("brightnessctl")
+                .arg("set")
+                .arg(format!(
+                    "{}%{}",
+                    delta.unsigned_abs(),
+                    if delta >= 0 { '+' } else { '-' }
+                ))
+                .status(),
+        };
+
+        status.is_ok_and(|s| s.success())
+    }
+}
+
+/// Gamma applied by `Perceptual` when mapping the user-facing percentage
+/// onto the hardware scale.
+const PERCEPTUAL_GAMMA: f64 = 2.2;
+
+fn linear_to_device(percent: u8) -> u8 {
+    (100.0 * (percent as f64 / 100.0).powf(PERCEPTUAL_GAMMA)).round() as u8
+}
+
+fn device_to_linear(percent: u8) -> u8 {
+    (100.0 * (percent as f64 / 100.0).powf(1.0 / PERCEPTUAL_GAMMA)).round() as u8
+}
+
+/// Wraps another `Backlight` to apply a perceptual (gamma) curve between
+/// the user-facing percentage and what's written to the hardware.
+pub struct Perceptual {
+    inner: Box<dyn Backlight>,
+}
+
+impl Perceptual {
+    pub fn new(inner: Box<dyn Backlight>) -> Self {
+        Self { inner }
+    }
+}
+
+impl Backlight for Perceptual {
+    fn get_brightness(&self) -> Option<u8> {
+        self.inner.get_brightness().map(device_to_linear)
+    }
+
+    fn set_brightness(&self, brightness: u8, fade_time: u32, steps: u32) -> bool {
+        self.inner
+            .set_brightness(linear_to_device(brightness), fade_time, steps)
+    }
+
+    /// Delegates through `inner.adjust_brightness` in device space (rather
+    /// than the default `set_brightness`-based implementation) so backends
+    /// like `Sysfs` still get to apply their own minimum-step guarantee;
+    /// otherwise the gamma curve can flatten several points of low-end
+    /// `delta` to the same device value and the hardware never moves.
+    fn adjust_brightness(&self, delta: i16, fade_time: u32, steps: u32) -> bool {
+        let Some(current_device) = self.inner.get_brightness() else {
+            return false;
+        };
+        let current_user = device_to_linear(current_device);
+        let target_user = (current_user as i16 + delta).clamp(0, 100) as u8;
+        let target_device = linear_to_device(target_user);
+
+        let mut device_delta = target_device as i16 - current_device as i16;
+        if delta != 0 && device_delta == 0 {
+            device_delta = delta.signum();
+        }
+
+        self.inner.adjust_brightness(device_delta, fade_time, steps)
+    }
+
+    fn watch_path(&self) -> Option<PathBuf> {
+        self.inner.watch_path()
+    }
+}
+
+/// Which family of backend to dispatch brightness operations through.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum BackendKind {
+    Program,
+    Sysfs,
+    Auto,
+}
+
+/// Resolve a `--backend`/`--program` selection (and optional `--device`)
+/// into a concrete `Backlight` implementation.
+pub fn resolve(kind: BackendKind, program: ProgramKind, device: Option<&str>) -> Box<dyn Backlight> {
+    let sysfs = || device.and_then(Sysfs::with_device).or_else(Sysfs::detect);
+
+    match kind {
+        BackendKind::Program => Box::new(ExternalProgram::new(program)),
+        BackendKind::Sysfs => match sysfs() {
+            Some(backend) => Box::new(backend),
+            None => {
+                eprintln!("Error: no usable sysfs backlight device found.");
+                std::process::exit(1);
+            }
+        },
+        BackendKind::Auto => match sysfs() {
+            Some(backend) if backend.is_writable() => Box::new(backend),
+            _ => Box::new(ExternalProgram::new(program)),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Builds a `Sysfs` backed by a throwaway directory under the system
+    /// temp dir, standing in for `/sys/class/backlight/<device>`.
+    fn fixture(max_brightness: u32, initial_raw: u32) -> Sysfs {
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let device_dir = std::env::temp_dir()
+            .join(format!("lxqt-brightness-notifier-test-{}-{}", std::process::id(), n));
+        fs::create_dir_all(&device_dir).unwrap();
+        fs::write(device_dir.join("max_brightness"), max_brightness.to_string()).unwrap();
+        fs::write(device_dir.join("brightness"), initial_raw.to_string()).unwrap();
+
+        Sysfs {
+            device_dir,
+            max_brightness,
+        }
+    }
+
+    #[test]
+    fn set_brightness_never_no_ops_on_a_nonzero_request() {
+        // max_brightness=7 means 1% rounds to raw 0, the same as the
+        // device's resting value of 0, unless the min-step guard kicks in.
+        let sysfs = fixture(7, 0);
+        assert!(sysfs.set_brightness(1, 0, 1));
+        assert_eq!(sysfs.raw_brightness(), Some(1));
+    }
+
+    #[test]
+    fn set_brightness_is_idempotent_when_already_at_the_requested_percentage() {
+        let sysfs = fixture(100, 50);
+        assert!(sysfs.set_brightness(50, 0, 1));
+        assert_eq!(sysfs.raw_brightness(), Some(50));
+    }
+
+    #[test]
+    fn adjust_brightness_never_no_ops_on_a_nonzero_delta() {
+        let sysfs = fixture(7, 0);
+        assert!(sysfs.adjust_brightness(1, 0, 1));
+        assert_eq!(sysfs.raw_brightness(), Some(1));
+    }
+
+    #[test]
+    fn perceptual_adjust_brightness_always_moves_the_hardware() {
+        // PERCEPTUAL_GAMMA = 2.2 rounds linear_to_device(p) to 0 for every
+        // p in 0..=8, so repeated +1% steps from a low starting point must
+        // still move the underlying sysfs device by at least one raw unit.
+        let sysfs = fixture(255, 0);
+        let perceptual = Perceptual::new(Box::new(sysfs));
+
+        let mut raw = perceptual.inner.get_brightness().unwrap();
+        for _ in 0..8 {
+            assert!(perceptual.adjust_brightness(1, 0, 1));
+            let next = perceptual.inner.get_brightness().unwrap();
+            assert!(next > raw, "adjust_brightness failed to move the device");
+            raw = next;
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::backlight::{BackendKind, ProgramKind};
+use crate::curve::{self, Point};
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Shape of the TOML config file on disk; fields mirror the CLI flags they
+/// provide a default for.
+#[derive(Debug, Default, Deserialize)]
+struct FileConfig {
+    timeout: Option<i32>,
+    fade_time: Option<u32>,
+    steps: Option<u32>,
+    backend: Option<String>,
+    program: Option<String>,
+    device: Option<String>,
+    curve: Option<Vec<String>>,
+}
+
+/// Defaults sourced from the config file, ready to be layered underneath
+/// whatever the user passed on the command line.
+#[derive(Default)]
+pub struct Config {
+    pub timeout: Option<i32>,
+    pub fade_time: Option<u32>,
+    pub steps: Option<u32>,
+    pub backend: Option<BackendKind>,
+    pub program: Option<ProgramKind>,
+    pub device: Option<String>,
+    pub curve: Vec<Point>,
+}
+
+/// Default config file path: `~/.config/lxqt-brightness-notifier/config.toml`.
+fn default_path() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME")?;
+    Some(Path::new(&home).join(".config/lxqt-brightness-notifier/config.toml"))
+}
+
+/// Load defaults from a TOML config file (`--config`, or the default path
+/// under `$HOME`). A missing file is not an error; a malformed one is
+/// reported but otherwise ignored so the tool still runs off CLI flags.
+pub fn load(explicit_path: Option<&str>) -> Config {
+    let path = explicit_path.map(PathBuf::from).or_else(default_path);
+
+    let Some(file) = path.and_then(|path| read(&path)) else {
+        return Config::default();
+    };
+
+    Config {
+        timeout: file.timeout,
+        fade_time: file.fade_time,
+        steps: file.steps,
+        backend: file.backend.as_deref().and_then(parse_value_enum),
+        program: file.program.as_deref().and_then(parse_value_enum),
+        device: file.device,
+        curve: file
+            .curve
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|s| match curve::parse_point(s) {
+                Ok(point) => Some(point),
+                Err(e) => {
+                    eprintln!("Warning: invalid curve point `{}` in config: {}.", s, e);
+                    None
+                }
+            })
+            .collect(),
+    }
+}
+
+fn read(path: &Path) -> Option<FileConfig> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    match toml::from_str(&contents) {
+        Ok(config) => Some(config),
+        Err(e) => {
+            eprintln!("Warning: failed to parse {}: {}.", path.display(), e);
+            None
+        }
+    }
+}
+
+fn parse_value_enum<T: ValueEnum>(s: &str) -> Option<T> {
+    T::from_str(s, true).ok()
+}
@@ -0,0 +1,178 @@
+/// A single (lux, brightness-percent) control point of the ambient-light
+/// curve used by `--auto`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Point {
+    pub lux: f64,
+    pub percent: f64,
+}
+
+/// The built-in lux -> brightness-percent curve, used when the user hasn't
+/// supplied (or hasn't fully overridden) their own `--curve` points.
+pub fn default_points() -> Vec<Point> {
+    vec![
+        Point { lux: 0.0, percent: 10.0 },
+        Point { lux: 10.0, percent: 20.0 },
+        Point { lux: 50.0, percent: 40.0 },
+        Point { lux: 200.0, percent: 60.0 },
+        Point { lux: 1000.0, percent: 80.0 },
+        Point { lux: 10000.0, percent: 100.0 },
+    ]
+}
+
+/// Merge user-supplied control points into the defaults, with a
+/// user-supplied point at the same lux value replacing the default, then
+/// sort the result by lux ascending as `evaluate` requires.
+pub fn merge(defaults: Vec<Point>, overrides: &[Point]) -> Vec<Point> {
+    let mut points = defaults;
+    for &over in overrides {
+        match points.iter_mut().find(|p| p.lux == over.lux) {
+            Some(existing) => *existing = over,
+            None => points.push(over),
+        }
+    }
+    points.sort_by(|a, b| a.lux.total_cmp(&b.lux));
+    points
+}
+
+/// Parse a single `--curve LUX:PCT` argument.
+pub fn parse_point(s: &str) -> Result<Point, String> {
+    let (lux, percent) = s
+        .split_once(':')
+        .ok_or_else(|| format!("expected LUX:PCT, got `{}`", s))?;
+
+    let lux: f64 = lux
+        .parse()
+        .map_err(|_| format!("invalid lux value `{}`", lux))?;
+    let percent: f64 = percent
+        .parse()
+        .map_err(|_| format!("invalid percent value `{}`", percent))?;
+
+    Ok(Point { lux, percent })
+}
+
+/// Evaluate the monotone-cubic (Fritsch-Carlson) spline through `points` at
+/// `lux`, clamping to the first/last point's percentage outside their range.
+pub fn evaluate(points: &[Point], lux: f64) -> f64 {
+    let Some(first) = points.first() else {
+        return 0.0;
+    };
+    let Some(last) = points.last() else {
+        return 0.0;
+    };
+
+    if lux <= first.lux {
+        return first.percent;
+    }
+    if lux >= last.lux {
+        return last.percent;
+    }
+
+    let i = points
+        .windows(2)
+        .position(|w| lux >= w[0].lux && lux <= w[1].lux)
+        .unwrap();
+
+    let tangents = fritsch_carlson_tangents(points);
+    let (x0, y0) = (points[i].lux, points[i].percent);
+    let (x1, y1) = (points[i + 1].lux, points[i + 1].percent);
+    let (m0, m1) = (tangents[i], tangents[i + 1]);
+
+    let h = x1 - x0;
+    let t = (lux - x0) / h;
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    // Cubic Hermite basis functions.
+    let h00 = 2.0 * t3 - 3.0 * t2 + 1.0;
+    let h10 = t3 - 2.0 * t2 + t;
+    let h01 = -2.0 * t3 + 3.0 * t2;
+    let h11 = t3 - t2;
+
+    h00 * y0 + h10 * h * m0 + h01 * y1 + h11 * h * m1
+}
+
+/// Compute per-key tangents for a monotone cubic Hermite spline, limiting
+/// the naive secant-average slopes so the interpolant never overshoots
+/// between control points (Fritsch-Carlson, 1980).
+fn fritsch_carlson_tangents(points: &[Point]) -> Vec<f64> {
+    let n = points.len();
+    let secants: Vec<f64> = points
+        .windows(2)
+        .map(|w| (w[1].percent - w[0].percent) / (w[1].lux - w[0].lux))
+        .collect();
+
+    let mut tangents: Vec<f64> = (0..n)
+        .map(|i| match (i.checked_sub(1), secants.get(i)) {
+            (Some(prev), Some(&next)) => {
+                let prev = secants[prev];
+                if prev.signum() != next.signum() || prev == 0.0 || next == 0.0 {
+                    0.0
+                } else {
+                    (prev + next) / 2.0
+                }
+            }
+            (None, Some(&next)) => next,
+            (Some(prev), None) => secants[prev],
+            (None, None) => 0.0,
+        })
+        .collect();
+
+    // Rescale each pair of tangents bracketing a secant so the cubic can't
+    // overshoot past either endpoint (Fritsch-Carlson's alpha/beta test).
+    for (k, &d_k) in secants.iter().enumerate() {
+        if d_k == 0.0 {
+            continue;
+        }
+        let alpha = tangents[k] / d_k;
+        let beta = tangents[k + 1] / d_k;
+        let magnitude = alpha.powi(2) + beta.powi(2);
+        if magnitude > 9.0 {
+            let tau = 3.0 / magnitude.sqrt();
+            tangents[k] = tau * alpha * d_k;
+            tangents[k + 1] = tau * beta * d_k;
+        }
+    }
+
+    tangents
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluate_clamps_outside_range() {
+        let points = default_points();
+        assert_eq!(evaluate(&points, -10.0), points.first().unwrap().percent);
+        assert_eq!(evaluate(&points, 100_000.0), points.last().unwrap().percent);
+    }
+
+    #[test]
+    fn evaluate_hits_control_points_exactly() {
+        let points = default_points();
+        for p in &points {
+            assert!((evaluate(&points, p.lux) - p.percent).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn evaluate_is_monotone_between_increasing_control_points() {
+        let points = default_points();
+        let mut lux = 0.0;
+        let mut prev = evaluate(&points, lux);
+        while lux < 10_000.0 {
+            lux += 25.0;
+            let next = evaluate(&points, lux);
+            assert!(next + 1e-9 >= prev, "spline dipped at lux={}", lux);
+            prev = next;
+        }
+    }
+
+    #[test]
+    fn merge_replaces_matching_lux_and_sorts() {
+        let merged = merge(default_points(), &[Point { lux: 50.0, percent: 33.0 }]);
+        let replaced = merged.iter().find(|p| p.lux == 50.0).unwrap();
+        assert_eq!(replaced.percent, 33.0);
+        assert!(merged.windows(2).all(|w| w[0].lux < w[1].lux));
+    }
+}
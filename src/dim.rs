@@ -0,0 +1,48 @@
+use crate::backlight::Backlight;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+/// How often the blocking wait checks for a shutdown signal while dimmed.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Fade the backend from its current brightness to `target`, over
+/// `fade_time` milliseconds in `steps` increments, sleeping between each
+/// step.
+fn fade_to(backend: &dyn Backlight, target: u8, fade_time: u32, steps: u32) {
+    let Some(current) = backend.get_brightness() else {
+        return;
+    };
+    if current == target {
+        return;
+    }
+
+    let steps = steps.max(1);
+    let sleep_per_step = Duration::from_millis((fade_time / steps) as u64);
+    let diff = target as i32 - current as i32;
+
+    for step in 1..=steps {
+        let intermediate = current as i32 + diff * step as i32 / steps as i32;
+        backend.set_brightness(intermediate.clamp(0, 100) as u8, 0, 1);
+        if step < steps {
+            thread::sleep(sleep_per_step);
+        }
+    }
+}
+
+/// Fade down to `target`, then block until SIGTERM/SIGINT, restoring the
+/// original brightness before returning. Intended to wrap a screen locker
+/// or idle timeout so the screen dims while locked and comes back after.
+pub fn run(backend: &dyn Backlight, target: u8, fade_time: u32, steps: u32, shutdown: &AtomicBool) {
+    let original = backend.get_brightness();
+
+    fade_to(backend, target, fade_time, steps);
+
+    while !shutdown.load(Ordering::Relaxed) {
+        thread::sleep(POLL_INTERVAL);
+    }
+
+    if let Some(original) = original {
+        fade_to(backend, original, fade_time, steps);
+    }
+}
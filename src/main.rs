@@ -1,13 +1,21 @@
+mod auto;
+mod backlight;
+mod config;
+mod curve;
+mod dim;
+mod signal;
+mod watch;
+
+use backlight::{BackendKind, Backlight, ProgramKind};
 use clap::Parser;
 use notify_rust::Notification;
-use std::process::Command;
 
 #[derive(clap::Parser, Debug)]
 #[command(
     author = "Manuel Albisu-Bouza",
     version = "1.0",
     about = "Brightness Notifier for LXQt",
-    long_about = "A simple command-line tool that displays a desktop notification when changing your display brightness using xbacklight. This program is intended to be used in conjunction with LXQt."
+    long_about = "A simple command-line tool that displays a desktop notification when changing your display brightness, via xbacklight or sysfs. This program is intended to be used in conjunction with LXQt."
 )]
 struct Args {
     /// Increase brightness level by a specified percentage
@@ -54,57 +62,110 @@ struct Args {
     get: bool,
 
     /// Notification timeout duration in milliseconds
-    /// (default: 2000 ms)
+    /// (default: 2000 ms, or the config file's value)
     #[arg(
         short = 't',
         long = "timeout",
-        default_value_t = 2000,
         value_name = "TIMEOUT DURATION IN MILLISECONDS"
     )]
-    timeout: i32,
+    timeout: Option<i32>,
 
     /// Fade time in milliseconds for changes in brightness level
-    /// (default: 250 ms, range: 0 - 60000 ms)
+    /// (default: 100 ms, or the config file's value; range: 0 - 60000 ms)
     #[arg(
         short = 'f',
         long = "fade",
-        default_value_t = 100,
         value_name = "FADE TIME IN MILLISECONDS",
         value_parser = clap::value_parser!(u32).range(..=60000)
     )]
-    fade_time: u32,
+    fade_time: Option<u32>,
 
     /// Number of steps in the fade for changes in brightness level
-    /// (default: 25 steps, range: 1 - 200 steps)
+    /// (default: 25 steps, or the config file's value; range: 1 - 200 steps)
     #[arg(
         short = 'p',
         long = "steps",
-        default_value_t = 25,
         value_name = "NUMBER OF STEPS IN FADE",
         value_parser = clap::value_parser!(u32).range(1..=200)
     )]
-    steps: u32,
-}
+    steps: Option<u32>,
 
-/// Retrieve the current brightness as a percentage.
-fn get_current_brightness() -> Option<u8> {
-    let output = Command::new("xbacklight")
-        .arg("-get")
-        .output()
-        .ok()?;
+    /// Backend used to query and drive the display brightness
+    /// (default: auto, or the config file's value; auto prefers sysfs when
+    /// a writable device is found)
+    #[arg(short = 'b', long = "backend", value_enum, value_name = "BACKEND")]
+    backend: Option<BackendKind>,
 
-    if !output.status.success() {
-        return None;
-    }
+    /// External brightness program to use when the backend is `program`
+    /// (or `auto` falls back to one); default: xbacklight, or the config
+    /// file's value
+    #[arg(long = "program", value_enum, value_name = "PROGRAM")]
+    program: Option<ProgramKind>,
+
+    /// sysfs backlight device name under /sys/class/backlight
+    /// (default: auto-detected)
+    #[arg(long = "device", value_name = "DEVICE")]
+    device: Option<String>,
+
+    /// Run as a daemon, notifying whenever the brightness changes from
+    /// any source (function keys, other tools, auto-brightness, ...)
+    #[arg(
+        short = 'w',
+        long = "watch",
+        conflicts_with_all = &["increase", "decrease", "set", "get"]
+    )]
+    watch: bool,
+
+    /// Polling interval in milliseconds used by --watch when the backend
+    /// has no file to watch via inotify (default: 500 ms)
+    #[arg(long = "interval", default_value_t = 500, value_name = "INTERVAL IN MILLISECONDS")]
+    interval: u64,
+
+    /// Drive the backend from an ambient light sensor instead of a
+    /// one-shot or manual change
+    #[arg(
+        short = 'a',
+        long = "auto",
+        conflicts_with_all = &["increase", "decrease", "set", "get", "watch"]
+    )]
+    auto: bool,
+
+    /// Ambient light sensor file to read lux from
+    /// (default: auto-detected under /sys/bus/iio/devices)
+    #[arg(long = "sensor", value_name = "PATH")]
+    sensor: Option<String>,
+
+    /// Add or override a lux:percent control point on the --auto curve
+    /// (repeatable, e.g. --curve 0:5 --curve 500:70)
+    #[arg(long = "curve", value_name = "LUX:PERCENT", value_parser = curve::parse_point)]
+    curve: Vec<curve::Point>,
+
+    /// Map the user-facing 0-100% onto a perceptual (gamma) curve before
+    /// writing to hardware, since brightness perception is nonlinear
+    #[arg(long = "perceptual")]
+    perceptual: bool,
+
+    /// Fade down to the given percentage, then block and restore the
+    /// original brightness on SIGTERM/SIGINT (for screen lockers/idle
+    /// handlers)
+    #[arg(
+        long = "dim",
+        value_name = "PERCENTAGE",
+        conflicts_with_all = &["increase", "decrease", "set", "get", "watch", "auto"],
+        value_parser = clap::value_parser!(u8).range(0..=100)
+    )]
+    dim: Option<u8>,
 
-    let output_str = String::from_utf8_lossy(&output.stdout);
-    let value: f64 = output_str.trim().parse().ok()?;
-    Some(value.round() as u8)
+    /// Path to a TOML config file supplying defaults for --timeout, --fade,
+    /// --steps, --backend, --program, --device and --curve
+    /// (default: ~/.config/lxqt-brightness-notifier/config.toml)
+    #[arg(long = "config", value_name = "PATH")]
+    config: Option<String>,
 }
 
 /// Display the current brightness in a desktop notification.
-fn display_notification(timeout: i32) -> Option<u8> {
-    let brightness = get_current_brightness()?;
+fn display_notification(backend: &dyn Backlight, timeout: i32) -> Option<u8> {
+    let brightness = backend.get_brightness()?;
     let body = format!("{}% Brightness", brightness);
     let icon = if brightness < 33 {
         "display-brightness-low"
@@ -131,40 +192,60 @@ fn display_notification(timeout: i32) -> Option<u8> {
 }
 
 /// Adjust the displays brightness level.
-fn adjust_brightness(args: &Args) -> bool {
-    let mut cmd = Command::new("xbacklight");
+fn adjust_brightness(backend: &dyn Backlight, args: &Args, fade_time: u32, steps: u32) -> bool {
     if let Some(inc) = args.increase {
-        cmd.arg("-inc").arg(inc.to_string());
+        backend.adjust_brightness(inc as i16, fade_time, steps)
     } else if let Some(dec) = args.decrease {
-        cmd.arg("-dec").arg(dec.to_string());
+        backend.adjust_brightness(-(dec as i16), fade_time, steps)
     } else {
         // No adjustment was requested.
-        return true;
+        true
     }
-    cmd.arg("-time").arg(args.fade_time.to_string());
-    cmd.arg("-steps").arg(args.steps.to_string());
-
-    let status = cmd.status();
-    status.map_or(false, |s| s.success())
-}
-
-/// Set the displays brightness level to a specified value.
-fn set_brightness(brightness: u8, args: &Args) -> bool {
-    let mut cmd = Command::new("xbacklight");
-    cmd.arg("-set").arg(brightness.to_string());
-    cmd.arg("-time").arg(args.fade_time.to_string());
-    cmd.arg("-steps").arg(args.steps.to_string());
-
-    let status = cmd.status();
-    status.map_or(false, |s| s.success())
 }
 
 fn main() {
     let args = Args::parse();
+    let config = config::load(args.config.as_deref());
+
+    let timeout = args.timeout.or(config.timeout).unwrap_or(2000);
+    let fade_time = args
+        .fade_time
+        .or(config.fade_time)
+        .unwrap_or(backlight::DEFAULT_FADE_TIME);
+    let steps = args.steps.or(config.steps).unwrap_or(backlight::DEFAULT_STEPS);
+    let backend_kind = args.backend.or(config.backend).unwrap_or(BackendKind::Auto);
+    let program = args.program.or(config.program).unwrap_or(ProgramKind::Xbacklight);
+    let device = args.device.clone().or(config.device);
+    let curve = if args.curve.is_empty() { config.curve } else { args.curve.clone() };
+
+    let mut backend = backlight::resolve(backend_kind, program, device.as_deref());
+    if args.perceptual {
+        backend = Box::new(backlight::Perceptual::new(backend));
+    }
+
+    // Run as a daemon, notifying on any brightness change, if requested.
+    if args.watch {
+        watch::run(backend.as_ref(), args.interval, timeout);
+        return;
+    }
+
+    // Drive the backend from an ambient light sensor, if requested.
+    if args.auto {
+        let shutdown = signal::install_shutdown_flag();
+        auto::run(backend.as_ref(), args.sensor.as_deref(), &curve, &shutdown);
+        return;
+    }
+
+    // Fade down and block until restored, if requested.
+    if let Some(target) = args.dim {
+        let shutdown = signal::install_shutdown_flag();
+        dim::run(backend.as_ref(), target, fade_time, steps, &shutdown);
+        return;
+    }
 
     // Retrieve and notify current brightness if --get flag is present.
     if args.get {
-        if display_notification(args.timeout).is_none() {
+        if display_notification(backend.as_ref(), timeout).is_none() {
             std::process::exit(1);
         }
 
@@ -173,19 +254,19 @@ fn main() {
 
     // Process brightness change requests.
     if let Some(target) = args.set {
-        if !set_brightness(target, &args) {
+        if !backend.set_brightness(target, fade_time, steps) {
             eprintln!("Error: failed to set brightness to {}.", target);
             std::process::exit(1);
         }
-    } else if args.increase.is_some() || args.decrease.is_some() {
-        if !adjust_brightness(&args) {
-            eprintln!("Error: failed to adjust the brightness level.");
-            std::process::exit(1);
-        }
+    } else if (args.increase.is_some() || args.decrease.is_some())
+        && !adjust_brightness(backend.as_ref(), &args, fade_time, steps)
+    {
+        eprintln!("Error: failed to adjust the brightness level.");
+        std::process::exit(1);
     }
 
     // Retrieve and notify current brightness after any changes.
-    if display_notification(args.timeout).is_none() {
+    if display_notification(backend.as_ref(), timeout).is_none() {
         std::process::exit(1);
     }
 }
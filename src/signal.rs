@@ -0,0 +1,15 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+/// Install handlers that flip an `AtomicBool` to true on SIGTERM/SIGINT, so
+/// long-running modes (`--watch`, `--auto`, `--dim`) can exit cleanly and
+/// run their cleanup instead of being killed mid-iteration.
+pub fn install_shutdown_flag() -> Arc<AtomicBool> {
+    let shutdown = Arc::new(AtomicBool::new(false));
+    for signal in [signal_hook::consts::SIGTERM, signal_hook::consts::SIGINT] {
+        if let Err(e) = signal_hook::flag::register(signal, Arc::clone(&shutdown)) {
+            eprintln!("Warning: failed to register signal handler: {}.", e);
+        }
+    }
+    shutdown
+}
@@ -0,0 +1,100 @@
+use crate::backlight::Backlight;
+use crate::signal::install_shutdown_flag;
+use inotify::{Inotify, WatchMask};
+use notify_rust::Notification;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+/// How long to wait after the first filesystem event before reading the
+/// new brightness, coalescing any further events fired by the same change.
+const DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Display the current brightness in a desktop notification, reusing
+/// notification id 1 so the popup updates in place rather than stacking.
+fn notify_brightness(brightness: u8, timeout: i32) {
+    let body = format!("{}% Brightness", brightness);
+    let icon = if brightness < 33 {
+        "display-brightness-low"
+    } else if brightness < 66 {
+        "display-brightness-medium"
+    } else {
+        "display-brightness-high"
+    };
+
+    if let Err(e) = Notification::new()
+        .summary("Brightness")
+        .body(&body)
+        .icon(icon)
+        .timeout(timeout)
+        .id(1)
+        .show()
+    {
+        eprintln!("Error: failed to display notification: {}.", e);
+    }
+}
+
+/// Watch the sysfs brightness file for changes via inotify, notifying
+/// whenever the level differs from what was last reported.
+fn watch_inotify(backend: &dyn Backlight, path: &std::path::Path, timeout: i32, shutdown: &AtomicBool) {
+    let mut inotify = match Inotify::init() {
+        Ok(inotify) => inotify,
+        Err(e) => {
+            eprintln!("Error: failed to initialize inotify: {}.", e);
+            return;
+        }
+    };
+
+    if let Err(e) = inotify.watches().add(path, WatchMask::MODIFY) {
+        eprintln!("Error: failed to watch {}: {}.", path.display(), e);
+        return;
+    }
+
+    let mut last = backend.get_brightness();
+    let mut buffer = [0; 1024];
+
+    while !shutdown.load(Ordering::Relaxed) {
+        if inotify.read_events_blocking(&mut buffer).is_err() {
+            break;
+        }
+
+        // Debounce: give any further events from the same change a chance
+        // to land, then drain them before reading the settled value.
+        thread::sleep(DEBOUNCE);
+        while inotify.read_events(&mut buffer).is_ok_and(|mut e| e.next().is_some()) {}
+
+        let current = backend.get_brightness();
+        if current.is_some() && current != last {
+            last = current;
+            notify_brightness(last.unwrap(), timeout);
+        }
+    }
+}
+
+/// Poll the backend for its current brightness every `interval`,
+/// notifying whenever the level differs from what was last reported.
+fn watch_poll(backend: &dyn Backlight, interval: Duration, timeout: i32, shutdown: &AtomicBool) {
+    let mut last = backend.get_brightness();
+
+    while !shutdown.load(Ordering::Relaxed) {
+        thread::sleep(interval);
+
+        let current = backend.get_brightness();
+        if current.is_some() && current != last {
+            last = current;
+            notify_brightness(last.unwrap(), timeout);
+        }
+    }
+}
+
+/// Run in daemon mode: notify whenever the brightness changes, regardless
+/// of what changed it. Uses inotify when the backend exposes a sysfs path
+/// to watch, otherwise falls back to polling on `interval`.
+pub fn run(backend: &dyn Backlight, interval_ms: u64, timeout: i32) {
+    let shutdown = install_shutdown_flag();
+
+    match backend.watch_path() {
+        Some(path) => watch_inotify(backend, &path, timeout, &shutdown),
+        None => watch_poll(backend, Duration::from_millis(interval_ms), timeout, &shutdown),
+    }
+}